@@ -0,0 +1,240 @@
+use glium::glutin;
+
+/// Holds the parameters needed to place an eye in the scene and project it onto the screen.
+/// `view_matrix` and `projection_matrix` are recomputed on demand from `eye`/`target`/`up` and
+/// `fov`/`aspect`/`near`/`far` respectively, so mutating the fields is enough to move the camera.
+/// # Example
+/// ```
+/// let mut camera = Camera::new([0.0, 0.0, 3.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0], 60f32.to_radians(), 16.0 / 9.0, 0.1, 100.0);
+/// scene.draw(&display, &program, &camera);
+/// ```
+pub struct Camera {
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+    pub up: [f32; 3],
+    pub fov: f32,
+    pub aspect: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Camera {
+    pub fn new(
+        eye: [f32; 3],
+        target: [f32; 3],
+        up: [f32; 3],
+        fov: f32,
+        aspect: f32,
+        near: f32,
+        far: f32,
+    ) -> Camera {
+        Camera {
+            eye,
+            target,
+            up,
+            fov,
+            aspect,
+            near,
+            far,
+        }
+    }
+
+    pub fn view_matrix(&self) -> [[f32; 4]; 4] {
+        let f = normalize(sub(self.target, self.eye));
+        let s = normalize(cross(f, self.up));
+        let v = cross(s, f);
+
+        [
+            [s[0], v[0], -f[0], 0.0],
+            [s[1], v[1], -f[1], 0.0],
+            [s[2], v[2], -f[2], 0.0],
+            [-dot(s, self.eye), -dot(v, self.eye), dot(f, self.eye), 1.0],
+        ]
+    }
+
+    pub fn projection_matrix(&self) -> [[f32; 4]; 4] {
+        let tan_half_fov = (self.fov / 2.0).tan();
+
+        [
+            [1.0 / (self.aspect * tan_half_fov), 0.0, 0.0, 0.0],
+            [0.0, 1.0 / tan_half_fov, 0.0, 0.0],
+            [
+                0.0,
+                0.0,
+                (self.far + self.near) / (self.near - self.far),
+                -1.0,
+            ],
+            [
+                0.0,
+                0.0,
+                2.0 * self.far * self.near / (self.near - self.far),
+                0.0,
+            ],
+        ]
+    }
+
+    /// Bundles `view_matrix`/`projection_matrix` into a uniforms value ready to be merged with
+    /// the rest of the uniforms passed to `Scene::draw`.
+    pub fn uniforms(&self) -> impl glium::uniforms::Uniforms {
+        glium::uniform! {
+            u_view: self.view_matrix(),
+            u_projection: self.projection_matrix(),
+        }
+    }
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let len = (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+    [a[0] / len, a[1] / len, a[2] / len]
+}
+
+/// Lets the mouse orbit a `Camera` around its `target`: dragging the left button changes azimuth
+/// and elevation, scrolling changes the orbit radius. Feed it the `glutin` events from the
+/// `event_loop` and call `apply` once per frame before drawing.
+pub struct OrbitControls {
+    pub azimuth: f32,
+    pub elevation: f32,
+    pub radius: f32,
+    dragging: bool,
+}
+
+impl OrbitControls {
+    pub fn new(radius: f32) -> OrbitControls {
+        OrbitControls {
+            azimuth: 0.0,
+            elevation: 0.0,
+            radius,
+            dragging: false,
+        }
+    }
+
+    pub fn handle_window_event(&mut self, event: &glutin::event::WindowEvent) {
+        match event {
+            glutin::event::WindowEvent::MouseInput {
+                state,
+                button: glutin::event::MouseButton::Left,
+                ..
+            } => {
+                self.dragging = *state == glutin::event::ElementState::Pressed;
+            }
+            glutin::event::WindowEvent::MouseWheel { delta, .. } => {
+                let scroll = match delta {
+                    glutin::event::MouseScrollDelta::LineDelta(_, y) => *y,
+                    glutin::event::MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+                };
+                self.radius = (self.radius - scroll * 0.5).max(0.1);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn handle_device_event(&mut self, event: &glutin::event::DeviceEvent) {
+        if let glutin::event::DeviceEvent::MouseMotion { delta: (dx, dy) } = event {
+            if self.dragging {
+                self.azimuth -= *dx as f32 * 0.005;
+                self.elevation = (self.elevation - *dy as f32 * 0.005).clamp(-1.5, 1.5);
+            }
+        }
+    }
+
+    /// Recomputes `camera.eye` from the current azimuth/elevation/radius, orbiting around
+    /// whatever `camera.target` is currently set to.
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.eye = [
+            camera.target[0] + self.radius * self.elevation.cos() * self.azimuth.sin(),
+            camera.target[1] + self.radius * self.elevation.sin(),
+            camera.target[2] + self.radius * self.elevation.cos() * self.azimuth.cos(),
+        ];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn assert_matrix_close(actual: [[f32; 4]; 4], expected: [[f32; 4]; 4]) {
+        for col in 0..4 {
+            for row in 0..4 {
+                assert!(
+                    (actual[col][row] - expected[col][row]).abs() < EPSILON,
+                    "column {} row {}: expected {}, got {}",
+                    col,
+                    row,
+                    expected[col][row],
+                    actual[col][row]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn view_matrix_looks_down_negative_z_from_the_z_axis() {
+        let camera = Camera::new([0.0, 0.0, 5.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0], 1.0, 1.0, 0.1, 100.0);
+
+        // eye on +Z looking at the origin: s = +X, v = +Y, f = -Z, so the view matrix is just a
+        // translation by -eye along Z
+        assert_matrix_close(
+            camera.view_matrix(),
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, -5.0, 1.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn projection_matrix_matches_the_standard_perspective_formula() {
+        let camera = Camera::new(
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0],
+            [0.0, 1.0, 0.0],
+            std::f32::consts::FRAC_PI_2,
+            1.0,
+            1.0,
+            10.0,
+        );
+
+        assert_matrix_close(
+            camera.projection_matrix(),
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, -11.0 / 9.0, -1.0],
+                [0.0, 0.0, -20.0 / 9.0, 0.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn orbit_controls_apply_places_the_eye_at_zero_azimuth_and_elevation() {
+        let mut camera = Camera::new([0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [0.0, 1.0, 0.0], 1.0, 1.0, 0.1, 100.0);
+        let controls = OrbitControls::new(5.0);
+
+        controls.apply(&mut camera);
+
+        // azimuth = elevation = 0.0, so the eye sits `radius` units along +Z from the target
+        assert!((camera.eye[0] - 1.0).abs() < EPSILON);
+        assert!((camera.eye[1] - 2.0).abs() < EPSILON);
+        assert!((camera.eye[2] - 8.0).abs() < EPSILON);
+    }
+}
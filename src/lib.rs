@@ -3,7 +3,8 @@ use glium::glutin;
 pub fn init_context() -> (glium::Display, glutin::event_loop::EventLoop<()>) {
     let event_loop = glutin::event_loop::EventLoop::new(); // WARN: was mutable oin the guide
     let wb = glutin::window::WindowBuilder::new();
-    let cb = glutin::ContextBuilder::new();
+    // a depth buffer is required for Scene's depth testing to have any effect
+    let cb = glutin::ContextBuilder::new().with_depth_buffer(24);
     let display = glium::Display::new(wb, cb, &event_loop).unwrap();
 
     (display, event_loop)
@@ -17,3 +18,21 @@ pub fn init_program(display: &glium::Display) -> glium::Program {
 
     program
 }
+
+/// The full-screen-quad program used by `Pipeline` passes: samples `u_texture` (the previous
+/// pass's output, or the pipeline's input) over the full viewport.
+pub fn init_post_process_program(display: &glium::Display) -> glium::Program {
+    let vertex_shader = include_str!("shaders/post_process.vertex.shader");
+    let fragment_shader = include_str!("shaders/post_process.fragment.shader");
+
+    glium::Program::from_source(display, vertex_shader, fragment_shader, None).unwrap()
+}
+
+/// The program used by `SpriteBatch::flush`: draws screen-space quads sampling `u_textures` at
+/// each sprite's layer.
+pub fn init_sprite_program(display: &glium::Display) -> glium::Program {
+    let vertex_shader = include_str!("shaders/sprite.vertex.shader");
+    let fragment_shader = include_str!("shaders/sprite.fragment.shader");
+
+    glium::Program::from_source(display, vertex_shader, fragment_shader, None).unwrap()
+}
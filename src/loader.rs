@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::scene::{Material, MaterialField, Vertex};
+
+/// Reads an OBJ file (and its companion MTL) from disk and turns it into the vertices, indices
+/// and materials a `Scene` needs. Every OBJ material becomes a `Material` carrying its diffuse
+/// color and, when the MTL points at a `map_Kd`, the decoded image loaded as a texture. Each
+/// emitted `Vertex` gets the `material_id` of the face it belongs to.
+pub fn load_obj(
+    display: &glium::Display,
+    path: &str,
+) -> (Vec<Vertex>, Vec<u32>, HashMap<u8, Material>) {
+    let (models, obj_materials) = tobj::load_obj(
+        path,
+        &tobj::LoadOptions {
+            triangulate: true,
+            single_index: true,
+            ..Default::default()
+        },
+    )
+    .expect("Failed to load OBJ file");
+    let obj_materials = obj_materials.expect("Failed to load the OBJ's MTL file");
+
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new("."));
+
+    // 0 is reserved for "no material", so OBJ material indices are shifted by one
+    let mut materials = HashMap::new();
+    let mut material_ids = HashMap::new();
+    for (obj_id, obj_material) in obj_materials.iter().enumerate() {
+        let mut diffuse = MaterialField::new(obj_material.diffuse);
+        if !obj_material.diffuse_texture.is_empty() {
+            let texture_path = base_dir.join(&obj_material.diffuse_texture);
+            diffuse.set_texture(load_texture(display, &texture_path));
+        }
+        let specularity = MaterialField::new(obj_material.shininess);
+
+        let id = (obj_id + 1) as u8;
+        materials.insert(id, Material::new(diffuse, specularity));
+        material_ids.insert(obj_id, id);
+    }
+
+    let mut vertices = vec![];
+    let mut indices = vec![];
+    for model in models {
+        let mesh = model.mesh;
+        let material_id = mesh
+            .material_id
+            .and_then(|obj_id| material_ids.get(&obj_id).copied())
+            .unwrap_or(0);
+
+        let offset = vertices.len() as u32;
+        for i in 0..mesh.positions.len() / 3 {
+            let uv = if mesh.texcoords.len() >= (i + 1) * 2 {
+                [mesh.texcoords[i * 2], mesh.texcoords[i * 2 + 1]]
+            } else {
+                [0.0, 0.0]
+            };
+
+            vertices.push(Vertex {
+                pos: [
+                    mesh.positions[i * 3],
+                    mesh.positions[i * 3 + 1],
+                    mesh.positions[i * 3 + 2],
+                ],
+                uv,
+                material_id,
+            });
+        }
+        indices.extend(mesh.indices.iter().map(|i| offset + i));
+    }
+
+    (vertices, indices, materials)
+}
+
+fn load_texture(display: &glium::Display, path: &Path) -> glium::texture::Texture2d {
+    let image = image::open(path)
+        .unwrap_or_else(|_| panic!("Failed to load texture {:?}", path))
+        .to_rgba8();
+    let dimensions = image.dimensions();
+    let raw = glium::texture::RawImage2d::from_raw_rgba_reversed(&image.into_raw(), dimensions);
+    glium::texture::Texture2d::new(display, raw).unwrap()
+}
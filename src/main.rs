@@ -4,24 +4,110 @@ extern crate glium;
 use glium::glutin;
 use scene::Vertex;
 
+mod camera;
+use camera::{Camera, OrbitControls};
+
 mod scene;
-use scene::Scene;
+use scene::{Material, MaterialField, Scene, TextureSource};
+
+mod loader;
+
+mod render_target;
+use render_target::RenderTarget;
+
+mod pipeline;
+use pipeline::{Pass, Pipeline};
+
+mod sprite_batch;
+use sprite_batch::{Sprite, SpriteBatch};
+
+/// A `TextureSource` that cycles its single material through a color gradient, one step per
+/// frame, e.g. standing in for a live video feed or a procedural animation.
+struct PulsingTextureSource {
+    frame: u32,
+}
+
+impl TextureSource for PulsingTextureSource {
+    fn next_frame(&mut self) -> Option<(Vec<u8>, u32, u32, glium::texture::ClientFormat)> {
+        self.frame += 1;
+        let phase = (self.frame as f32 * 0.05).sin() * 0.5 + 0.5;
+        let channel = (phase * 255.0) as u8;
+        Some((
+            vec![channel, 0, 255 - channel, 255],
+            1,
+            1,
+            glium::texture::ClientFormat::U8U8U8U8,
+        ))
+    }
+}
 
 fn main() {
     // init
     let (display, event_loop) = opengl::init_context();
     let program = opengl::init_program(&display);
+    let post_process_program = opengl::init_post_process_program(&display);
+    let sprite_program = opengl::init_sprite_program(&display);
+
+    // the scene is rendered into this offscreen target instead of straight onto the window, so a
+    // post-processing `Pipeline` can run on it first
+    let (width, height) = display.get_framebuffer_dimensions();
+    let mut scene_target = RenderTarget::with_depth(&display, width, height);
+    let mut pipeline = Pipeline::new(&display);
+    pipeline.add_pass(Pass::new(post_process_program, None));
+
+    // a single white pixel, batched as a small HUD marker in the corner of the screen every frame
+    let white_pixel = glium::texture::RawImage2d::from_raw_rgba(vec![255u8, 255, 255, 255], (1, 1));
+    let hud_textures = glium::texture::SrgbTexture2dArray::new(&display, vec![white_pixel]).unwrap();
+    let mut hud_batch = SpriteBatch::new(&display, hud_textures);
 
     let mut scene = Scene::new();
+    // material 1 starts with a flat diffuse value; update_texture overwrites it with a live
+    // texture once `pulsing_source` produces its first frame
+    scene.add_material(Material::new(
+        MaterialField::new([1.0, 1.0, 1.0]),
+        MaterialField::new(0.0),
+    ));
+    let mut pulsing_source = PulsingTextureSource { frame: 0 };
 
-    // mesh
-    scene.set_vertices(vec![
-        Vertex::new(-0.5, -0.5, 0.0),
-        Vertex::new(-0.5, 0.5, 0.0),
-        Vertex::new(0.5, 0.5, 0.0),
-        Vertex::new(0.5, -0.5, 0.0),
-    ]);
-    scene.set_indices(vec![0, 1, 2, 0, 2, 3]);
+    let mut camera = Camera::new(
+        [0.0, 0.0, 3.0],
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        60f32.to_radians(),
+        16.0 / 9.0,
+        0.1,
+        100.0,
+    );
+    let mut controls = OrbitControls::new(3.0);
+
+    // an OBJ/MTL path passed as the first argument replaces the hardcoded quad below, e.g.
+    // `cargo run -- assets/model.obj`
+    let obj_path = std::env::args().nth(1);
+
+    if let Some(path) = &obj_path {
+        scene.load_obj(&display, path);
+    } else {
+        // mesh, shaded with material 1 so the pulsing texture (see below) is visible on it
+        scene.set_vertices(vec![
+            Vertex {
+                material_id: 1,
+                ..Vertex::new(-0.5, -0.5, 0.0)
+            },
+            Vertex {
+                material_id: 1,
+                ..Vertex::new(-0.5, 0.5, 0.0)
+            },
+            Vertex {
+                material_id: 1,
+                ..Vertex::new(0.5, 0.5, 0.0)
+            },
+            Vertex {
+                material_id: 1,
+                ..Vertex::new(0.5, -0.5, 0.0)
+            },
+        ]);
+        scene.set_indices(vec![0, 1, 2, 0, 2, 3]);
+    }
     scene.set_uniforms(glium::uniform! {
         u_color: [1.0, 1.0, 1.0] as [f32; 3]
     });
@@ -36,18 +122,41 @@ fn main() {
         // here we receive events
         match ev {
             // windows related events
-            glutin::event::Event::WindowEvent { event, .. } => match event {
-                // the window will be closed
-                glutin::event::WindowEvent::CloseRequested => {
-                    *control_flow = glutin::event_loop::ControlFlow::Exit;
-                    return;
+            glutin::event::Event::WindowEvent { event, .. } => {
+                match &event {
+                    // the window will be closed
+                    glutin::event::WindowEvent::CloseRequested => {
+                        *control_flow = glutin::event_loop::ControlFlow::Exit;
+                        return;
+                    }
+                    // other events
+                    _ => {}
                 }
-                // other events
-                _ => return,
-            },
+                // let the orbit controls react to mouse drag/scroll
+                controls.handle_window_event(&event);
+            }
+            // raw device motion, used by the orbit controls for mouse drag
+            glutin::event::Event::DeviceEvent { event, .. } => {
+                controls.handle_device_event(&event);
+            }
             glutin::event::Event::MainEventsCleared => {
-                // draw scene
-                scene.draw(&display, &program);
+                // orbit the camera around the scene, draw it into the offscreen target, then run
+                // the post-processing pipeline to present it
+                controls.apply(&mut camera);
+                scene.update_texture(&display, 1, &mut pulsing_source);
+                scene.draw_to(&display, &mut scene_target, &program, &camera);
+
+                // draw a small HUD marker on top of the scene in the same offscreen target,
+                // batched through a SpriteBatch, so the pipeline presents both in one frame
+                hud_batch.push(Sprite {
+                    pos: [-1.0, 0.9],
+                    size: [0.05, 0.05],
+                    uv_rect: [0.0, 0.0, 1.0, 1.0],
+                    layer: 0.0,
+                });
+                hud_batch.flush(&mut scene_target.framebuffer(&display), &sprite_program);
+
+                pipeline.run(&display, &scene_target.color);
             }
             // other kinds of events
             _ => (),
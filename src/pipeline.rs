@@ -0,0 +1,122 @@
+use glium::Surface;
+
+use crate::render_target::RenderTarget;
+
+#[derive(Copy, Clone)]
+struct QuadVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+}
+
+glium::implement_vertex!(QuadVertex, pos, uv);
+
+const QUAD_VERTICES: [QuadVertex; 4] = [
+    QuadVertex {
+        pos: [-1.0, -1.0],
+        uv: [0.0, 0.0],
+    },
+    QuadVertex {
+        pos: [1.0, -1.0],
+        uv: [1.0, 0.0],
+    },
+    QuadVertex {
+        pos: [1.0, 1.0],
+        uv: [1.0, 1.0],
+    },
+    QuadVertex {
+        pos: [-1.0, 1.0],
+        uv: [0.0, 1.0],
+    },
+];
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+
+/// One stage of a `Pipeline`: a fragment `program` run over a full-screen quad, sampling the
+/// previous pass's output texture as `u_texture`. `output` is where this pass renders to; `None`
+/// means "the display", which only makes sense for the last pass.
+pub struct Pass {
+    pub program: glium::Program,
+    pub output: Option<RenderTarget>,
+}
+
+impl Pass {
+    pub fn new(program: glium::Program, output: Option<RenderTarget>) -> Pass {
+        Pass { program, output }
+    }
+}
+
+/// Chains `Pass`es into a multi-pass post-processing pipeline: each pass samples the texture
+/// produced by the previous one (or the initial `input` texture, for the first pass) and renders
+/// a full-screen quad with its own fragment program.
+pub struct Pipeline {
+    passes: Vec<Pass>,
+    quad_vertices: glium::VertexBuffer<QuadVertex>,
+    quad_indices: glium::IndexBuffer<u32>,
+}
+
+impl Pipeline {
+    pub fn new(display: &glium::Display) -> Pipeline {
+        Pipeline {
+            passes: vec![],
+            quad_vertices: glium::VertexBuffer::new(display, &QUAD_VERTICES).unwrap(),
+            quad_indices: glium::IndexBuffer::new(
+                display,
+                glium::index::PrimitiveType::TrianglesList,
+                &QUAD_INDICES,
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn add_pass(&mut self, pass: Pass) {
+        self.passes.push(pass);
+    }
+
+    /// Runs every pass in order, feeding `input` as the first pass's `u_texture`.
+    pub fn run(&mut self, display: &glium::Display, input: &glium::texture::Texture2d) {
+        for i in 0..self.passes.len() {
+            // split so the previous pass's output can be read while this pass is borrowed
+            // mutably, instead of indexing `self.passes` twice in ways the borrow checker
+            // can't prove are disjoint
+            let (before, after) = self.passes.split_at_mut(i);
+            let pass = &mut after[0];
+
+            let source: &glium::texture::Texture2d = before
+                .last()
+                .and_then(|previous| previous.output.as_ref())
+                .map(|target| &target.color)
+                .unwrap_or(input);
+            let sampler = source
+                .sampled()
+                .magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear);
+            let uniforms = glium::uniform! { u_texture: sampler };
+
+            match &mut pass.output {
+                Some(target) => {
+                    let mut framebuffer = target.framebuffer(display);
+                    framebuffer
+                        .draw(
+                            &self.quad_vertices,
+                            &self.quad_indices,
+                            &pass.program,
+                            &uniforms,
+                            &Default::default(),
+                        )
+                        .unwrap();
+                }
+                None => {
+                    let mut frame = display.draw();
+                    frame
+                        .draw(
+                            &self.quad_vertices,
+                            &self.quad_indices,
+                            &pass.program,
+                            &uniforms,
+                            &Default::default(),
+                        )
+                        .unwrap();
+                    frame.finish().unwrap();
+                }
+            }
+        }
+    }
+}
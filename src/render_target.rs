@@ -0,0 +1,36 @@
+/// An offscreen color buffer (with an optional depth buffer) a `Scene` can be drawn into instead
+/// of the window, via `Scene::draw_to`. The resulting `color` texture can then be sampled by a
+/// later render pass, e.g. in a `Pipeline`.
+pub struct RenderTarget {
+    pub color: glium::texture::Texture2d,
+    pub depth: Option<glium::texture::DepthTexture2d>,
+}
+
+impl RenderTarget {
+    pub fn new(display: &glium::Display, width: u32, height: u32) -> RenderTarget {
+        RenderTarget {
+            color: glium::texture::Texture2d::empty(display, width, height).unwrap(),
+            depth: None,
+        }
+    }
+
+    pub fn with_depth(display: &glium::Display, width: u32, height: u32) -> RenderTarget {
+        RenderTarget {
+            color: glium::texture::Texture2d::empty(display, width, height).unwrap(),
+            depth: Some(glium::texture::DepthTexture2d::empty(display, width, height).unwrap()),
+        }
+    }
+
+    pub(crate) fn framebuffer<'a>(
+        &'a self,
+        display: &glium::Display,
+    ) -> glium::framebuffer::SimpleFrameBuffer<'a> {
+        match &self.depth {
+            Some(depth) => {
+                glium::framebuffer::SimpleFrameBuffer::with_depth_buffer(display, &self.color, depth)
+                    .unwrap()
+            }
+            None => glium::framebuffer::SimpleFrameBuffer::new(display, &self.color).unwrap(),
+        }
+    }
+}
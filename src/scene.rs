@@ -1,5 +1,14 @@
 use std::collections::HashMap;
 
+use glium::uniforms::AsUniformValue;
+
+use crate::camera::Camera;
+use crate::render_target::RenderTarget;
+
+/// Size of the `u_diffuse`/`u_specularity` uniform arrays, matching `Vertex::material_id`'s `u8` range.
+/// These are plain `vec3`/`float` arrays, which GLSL 140 allows indexing with a runtime value.
+const MAX_MATERIALS: usize = 256;
+
 /// Use a scene to store information on the 3D environment and draw it on screen.
 /// # Example
 /// ```
@@ -23,7 +32,8 @@ use std::collections::HashMap;
 ///     u_color: [1.0, 1.0, 1.0] as [f32; 3]
 /// });
 ///
-/// scene.draw(&display, &program); // the magin behind the scenes... ...
+/// let camera = Camera::new([0.0, 0.0, 3.0], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0], 60f32.to_radians(), 16.0 / 9.0, 0.1, 100.0);
+/// scene.draw(&display, &program, &camera); // the magin behind the scenes... ...
 /// ```
 pub struct Scene<U: glium::uniforms::Uniforms> {
     vertices: Vec<Vertex>,
@@ -33,6 +43,13 @@ pub struct Scene<U: glium::uniforms::Uniforms> {
     uniforms: Option<U>,
     materials: HashMap<u8, Material>,
     id_counter: u8,
+    draw_config: DrawConfig,
+    /// Every textured material's diffuse texture packed into one array texture, plus the
+    /// material id each layer came from. Rebuilt lazily by `update_texture_array`, the same way
+    /// `vertex_buffer`/`index_buffer` are rebuilt from `vertices`/`indices`.
+    texture_array: Option<glium::texture::SrgbTexture2dArray>,
+    texture_array_materials: Vec<u8>,
+    material_uniform_names: MaterialUniformNames,
 }
 
 impl<U: glium::uniforms::Uniforms> Scene<U> {
@@ -45,6 +62,10 @@ impl<U: glium::uniforms::Uniforms> Scene<U> {
             vertex_buffer: None,
             index_buffer: None,
             uniforms: None,
+            draw_config: DrawConfig::default(),
+            texture_array: None,
+            texture_array_materials: vec![],
+            material_uniform_names: MaterialUniformNames::new(),
         }
     }
 
@@ -62,17 +83,111 @@ impl<U: glium::uniforms::Uniforms> Scene<U> {
 
     pub fn set_materials(&mut self, materials: HashMap<u8, Material>) {
         self.materials = materials;
+        self.texture_array = None;
     }
 
     pub fn add_material(&mut self, material: Material) {
         self.id_counter += 1;
         self.materials.insert(self.id_counter, material);
+        self.texture_array = None;
     }
 
     pub fn set_uniforms(&mut self, uniforms: U) {
         self.uniforms = Some(uniforms);
     }
 
+    pub fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+
+    /// Rebuilds `texture_array` from every textured material's diffuse texture, unless it's
+    /// already up to date. GLSL 140 can't index a `sampler2D[]` array uniform by a runtime value,
+    /// but indexing the layer of a single `sampler2DArray` at runtime is legal, so every textured
+    /// material gets its own layer instead of only the first one found getting bound.
+    ///
+    /// All layers of a `Texture2dArray` must share the same dimensions; every diffuse texture
+    /// packed in here is expected to already match the first one found.
+    fn update_texture_array(&mut self, display: &glium::Display) {
+        if self.texture_array.is_some() {
+            return;
+        }
+
+        let mut textured: Vec<(u8, &glium::texture::Texture2d)> = self
+            .materials
+            .iter()
+            .filter_map(|(&id, material)| material.diffuse.texture.as_ref().map(|tex| (id, tex)))
+            .collect();
+        textured.sort_by_key(|(id, _)| *id);
+
+        if textured.is_empty() {
+            self.texture_array_materials.clear();
+            return;
+        }
+
+        let layers: Vec<glium::texture::RawImage2d<u8>> = textured
+            .iter()
+            .map(|(_, texture)| texture.read())
+            .collect();
+
+        self.texture_array_materials = textured.iter().map(|(id, _)| *id).collect();
+        self.texture_array =
+            Some(glium::texture::SrgbTexture2dArray::new(display, layers).unwrap());
+    }
+
+    /// Packs `materials` into the uniform arrays the shaders index by `material_id`: `u_diffuse`
+    /// and `u_specularity` hold every material's flat values, and `u_diffuse_layer` holds the
+    /// layer of `texture_array` each material's diffuse texture lives at (or `-1` when it has
+    /// none).
+    fn material_uniforms(&self) -> MaterialUniforms {
+        let mut diffuse = [[0.0f32; 3]; MAX_MATERIALS];
+        let mut specularity = [0.0f32; MAX_MATERIALS];
+        let mut diffuse_layer = [-1i32; MAX_MATERIALS];
+
+        for (&id, material) in &self.materials {
+            diffuse[id as usize] = material.diffuse.value.unwrap_or([0.0, 0.0, 0.0]);
+            specularity[id as usize] = material.specularity.value.unwrap_or(0.0);
+        }
+        for (layer, &id) in self.texture_array_materials.iter().enumerate() {
+            diffuse_layer[id as usize] = layer as i32;
+        }
+
+        MaterialUniforms {
+            diffuse,
+            specularity,
+            diffuse_layer,
+            texture_array: self.texture_array.as_ref(),
+            names: &self.material_uniform_names,
+        }
+    }
+
+    /// Pulls the next available frame out of `source` and uploads it as the diffuse texture of
+    /// the material `material_id`, e.g. for a live video texture mapped onto geometry. Call this
+    /// once per frame, before `draw`. Does nothing if `source` has no new frame ready yet.
+    pub fn update_texture<S: TextureSource>(
+        &mut self,
+        display: &glium::Display,
+        material_id: u8,
+        source: &mut S,
+    ) {
+        if let Some(material) = self.materials.get_mut(&material_id) {
+            if let Some((data, width, height, format)) = source.next_frame() {
+                material
+                    .diffuse
+                    .update_texture_from_raw(display, &data, width, height, format);
+                self.texture_array = None;
+            }
+        }
+    }
+
+    /// Loads an OBJ file (and its companion MTL) from disk, replacing this scene's vertices,
+    /// indices and materials with the ones it describes.
+    pub fn load_obj(&mut self, display: &glium::Display, path: &str) {
+        let (vertices, indices, materials) = crate::loader::load_obj(display, path);
+        self.set_vertices(vertices);
+        self.set_indices(indices);
+        self.set_materials(materials);
+    }
+
     pub fn get_vertex_buffer(&self, display: &glium::Display) -> glium::VertexBuffer<Vertex> {
         glium::VertexBuffer::new(display, &self.vertices).unwrap()
     }
@@ -102,23 +217,42 @@ impl<U: glium::uniforms::Uniforms> Scene<U> {
         }
     }
 
-    pub fn draw(&mut self, display: &glium::Display, program: &glium::Program) {
+    pub fn draw(&mut self, display: &glium::Display, program: &glium::Program, camera: &Camera) {
         self.update_vertex_buffer(display);
         self.update_index_buffer(display);
+        self.update_texture_array(display);
         if let Some(vb) = &self.vertex_buffer {
             if let Some(ib) = &self.index_buffer {
                 if let Some(un) = &self.uniforms {
                     // let's draw something
                     let mut target = display.draw(); // initialize a new FrameBuffer
                     use glium::Surface;
-                    target.clear_color(0.0, 0.0, 1.0, 1.0);
+                    target.clear_color_and_depth((0.0, 0.0, 1.0, 1.0), 1.0);
+                    // every mesh is drawn at the origin for now, so u_model is the identity matrix
+                    let model_uniforms = glium::uniform! {
+                        u_model: [
+                            [1.0f32, 0.0, 0.0, 0.0],
+                            [0.0, 1.0, 0.0, 0.0],
+                            [0.0, 0.0, 1.0, 0.0],
+                            [0.0, 0.0, 0.0, 1.0],
+                        ]
+                    };
+                    let cutoff_uniforms = glium::uniform! {
+                        u_alpha_cutoff: self.draw_config.alpha_cutoff.unwrap_or(-1.0)
+                    };
                     target
                         .draw(
                             vb,
                             ib,
                             program,
-                            un, // &glium::uniforms::EmptyUniforms
-                            &Default::default(),
+                            &(
+                                camera.uniforms(),
+                                model_uniforms,
+                                cutoff_uniforms,
+                                self.material_uniforms(),
+                                un,
+                            ), // &glium::uniforms::EmptyUniforms
+                            &self.draw_config.draw_parameters(),
                         )
                         .unwrap();
                     // draw the FrameBuffer and destroy it
@@ -133,6 +267,176 @@ impl<U: glium::uniforms::Uniforms> Scene<U> {
             panic!("Impossible to find any vertex buffer for this scene");
         }
     }
+
+    /// Like `draw`, but renders into an offscreen `RenderTarget` instead of the window, e.g. for
+    /// shadow maps or as the input of a post-processing `Pipeline`.
+    pub fn draw_to(
+        &mut self,
+        display: &glium::Display,
+        target: &mut RenderTarget,
+        program: &glium::Program,
+        camera: &Camera,
+    ) {
+        self.update_vertex_buffer(display);
+        self.update_index_buffer(display);
+        self.update_texture_array(display);
+        if let Some(vb) = &self.vertex_buffer {
+            if let Some(ib) = &self.index_buffer {
+                if let Some(un) = &self.uniforms {
+                    use glium::Surface;
+                    let mut framebuffer = target.framebuffer(display);
+                    framebuffer.clear_color_and_depth((0.0, 0.0, 1.0, 1.0), 1.0);
+                    let model_uniforms = glium::uniform! {
+                        u_model: [
+                            [1.0f32, 0.0, 0.0, 0.0],
+                            [0.0, 1.0, 0.0, 0.0],
+                            [0.0, 0.0, 1.0, 0.0],
+                            [0.0, 0.0, 0.0, 1.0],
+                        ]
+                    };
+                    let cutoff_uniforms = glium::uniform! {
+                        u_alpha_cutoff: self.draw_config.alpha_cutoff.unwrap_or(-1.0)
+                    };
+                    framebuffer
+                        .draw(
+                            vb,
+                            ib,
+                            program,
+                            &(
+                                camera.uniforms(),
+                                model_uniforms,
+                                cutoff_uniforms,
+                                self.material_uniforms(),
+                                un,
+                            ),
+                            &self.draw_config.draw_parameters(),
+                        )
+                        .unwrap();
+                } else {
+                    panic!("Cannot draw before specifing uniforms");
+                }
+            } else {
+                panic!("Impossible to find any index buffer for this scene");
+            }
+        } else {
+            panic!("Impossible to find any vertex buffer for this scene");
+        }
+    }
+}
+
+/// The `u_diffuse[i]`/`u_specularity[i]`/`u_diffuse_layer[i]` uniform names for every material id,
+/// computed once (in `Scene::new`) instead of on every `MaterialUniforms::visit_values` call: with
+/// `MAX_MATERIALS` at 256, formatting all three arrays' names every frame would mean 768 heap
+/// allocations per draw call for no reason, since the names never change.
+struct MaterialUniformNames {
+    diffuse: Vec<String>,
+    specularity: Vec<String>,
+    diffuse_layer: Vec<String>,
+}
+
+impl MaterialUniformNames {
+    fn new() -> MaterialUniformNames {
+        MaterialUniformNames {
+            diffuse: (0..MAX_MATERIALS).map(|id| format!("u_diffuse[{}]", id)).collect(),
+            specularity: (0..MAX_MATERIALS)
+                .map(|id| format!("u_specularity[{}]", id))
+                .collect(),
+            diffuse_layer: (0..MAX_MATERIALS)
+                .map(|id| format!("u_diffuse_layer[{}]", id))
+                .collect(),
+        }
+    }
+}
+
+/// Uniforms built by `Scene::material_uniforms`: flat `diffuse`/`specularity` values for every
+/// material id, the `texture_array` layer each material's diffuse texture lives at (`-1` for
+/// none), and `texture_array` itself, sampled in the shader as `texture(sampler, vec3(uv,
+/// layer))`. Unlike a `sampler2D[]` array uniform, GLSL 140 allows picking the layer of a
+/// `sampler2DArray` with a runtime value, so every textured material can be bound at once instead
+/// of only the first one found.
+struct MaterialUniforms<'a> {
+    diffuse: [[f32; 3]; MAX_MATERIALS],
+    specularity: [f32; MAX_MATERIALS],
+    diffuse_layer: [i32; MAX_MATERIALS],
+    texture_array: Option<&'a glium::texture::SrgbTexture2dArray>,
+    names: &'a MaterialUniformNames,
+}
+
+impl<'a> glium::uniforms::Uniforms for MaterialUniforms<'a> {
+    fn visit_values<'b, F: FnMut(&str, glium::uniforms::UniformValue<'b>)>(&'b self, mut output: F) {
+        for id in 0..MAX_MATERIALS {
+            output(
+                &self.names.diffuse[id],
+                glium::uniforms::UniformValue::Vec3(self.diffuse[id]),
+            );
+            output(
+                &self.names.specularity[id],
+                glium::uniforms::UniformValue::Float(self.specularity[id]),
+            );
+            output(
+                &self.names.diffuse_layer[id],
+                glium::uniforms::UniformValue::SignedInt(self.diffuse_layer[id]),
+            );
+        }
+
+        match self.texture_array {
+            Some(texture_array) => {
+                output("u_diffuse_textures", texture_array.as_uniform_value());
+                output("u_has_diffuse_textures", glium::uniforms::UniformValue::Bool(true));
+            }
+            None => {
+                output("u_has_diffuse_textures", glium::uniforms::UniformValue::Bool(false));
+            }
+        }
+    }
+}
+
+/// Controls how a `Scene` is rasterized: depth testing/writing, back-face culling and alpha
+/// blending. The defaults (depth test on, culling off, blending off) suit opaque 3D meshes
+/// with consistent winding; enable `backface_culling` once your meshes are wound for it,
+/// set `blend` for transparent surfaces, and set `alpha_cutoff` for cutout surfaces (foliage,
+/// fences).
+pub struct DrawConfig {
+    pub depth_test: bool,
+    pub backface_culling: bool,
+    pub blend: Option<glium::Blend>,
+    /// Fragments with alpha below this threshold are discarded in the fragment shader. `None`
+    /// disables the cutout.
+    pub alpha_cutoff: Option<f32>,
+}
+
+impl Default for DrawConfig {
+    fn default() -> DrawConfig {
+        DrawConfig {
+            depth_test: true,
+            backface_culling: false,
+            blend: None,
+            alpha_cutoff: None,
+        }
+    }
+}
+
+impl DrawConfig {
+    fn draw_parameters(&self) -> glium::DrawParameters {
+        glium::DrawParameters {
+            depth: glium::Depth {
+                test: if self.depth_test {
+                    glium::draw_parameters::DepthTest::IfLess
+                } else {
+                    glium::draw_parameters::DepthTest::Overwrite
+                },
+                write: self.depth_test,
+                ..Default::default()
+            },
+            backface_culling: if self.backface_culling {
+                glium::draw_parameters::BackfaceCullingMode::CullClockwise
+            } else {
+                glium::draw_parameters::BackfaceCullingMode::CullingDisabled
+            },
+            blend: self.blend.unwrap_or_default(),
+            ..Default::default()
+        }
+    }
 }
 
 pub struct Material {
@@ -173,6 +477,51 @@ impl<T> MaterialField<T> {
     pub fn clear_texture(&mut self) {
         self.texture = None;
     }
+
+    /// Uploads `data` as this field's texture, reusing the existing GPU allocation when its
+    /// dimensions already match instead of reallocating one every call. Use this instead of
+    /// `set_texture` for per-frame sources (camera feeds, decoded video, procedural buffers),
+    /// where reallocating a `Texture2d` every frame would churn the GPU.
+    pub fn update_texture_from_raw(
+        &mut self,
+        display: &glium::Display,
+        data: &[u8],
+        width: u32,
+        height: u32,
+        format: glium::texture::ClientFormat,
+    ) {
+        let matches_existing_size = self
+            .texture
+            .as_ref()
+            .map_or(false, |texture| texture.width() == width && texture.height() == height);
+
+        let raw = glium::texture::RawImage2d {
+            data: std::borrow::Cow::Borrowed(data),
+            width,
+            height,
+            format,
+        };
+
+        if matches_existing_size {
+            let rect = glium::Rect {
+                left: 0,
+                bottom: 0,
+                width,
+                height,
+            };
+            self.texture.as_ref().unwrap().write(rect, raw);
+        } else {
+            self.texture = Some(glium::texture::Texture2d::new(display, raw).unwrap());
+        }
+    }
+}
+
+/// A live source of texture frames, e.g. a channel fed by a video decoder or a procedural
+/// generator, that a `Scene` can pull from once per frame via `Scene::update_texture`.
+pub trait TextureSource {
+    /// Returns the next frame's raw pixel data, width, height and format, or `None` if no new
+    /// frame is ready yet.
+    fn next_frame(&mut self) -> Option<(Vec<u8>, u32, u32, glium::texture::ClientFormat)>;
 }
 
 /// We can expand the definition of a Vertex with other informations: normal, uv, color...
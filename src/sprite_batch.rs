@@ -0,0 +1,124 @@
+use glium::Surface;
+
+const MAX_SPRITES_PER_BATCH: usize = 1024;
+const VERTS_PER_SPRITE: usize = 4;
+const INDICES_PER_SPRITE: usize = 6;
+
+#[derive(Copy, Clone)]
+struct SpriteVertex {
+    pos: [f32; 2],
+    uv: [f32; 2],
+    layer: f32,
+}
+
+glium::implement_vertex!(SpriteVertex, pos, uv, layer);
+
+/// A quad pushed into a `SpriteBatch`: placed at `pos` with the given `size`, sampling `uv_rect`
+/// (`[u0, v0, u1, v1]`) from `layer` of the batch's texture array.
+pub struct Sprite {
+    pub pos: [f32; 2],
+    pub size: [f32; 2],
+    pub uv_rect: [f32; 4],
+    pub layer: f32,
+}
+
+/// Batches up to `MAX_SPRITES_PER_BATCH` quads per draw call into a persistent vertex buffer, so
+/// drawing thousands of sprites a frame costs a handful of draw calls instead of one per sprite.
+/// Backed by a `SrgbTexture2dArray` atlas so sprites sampling different layers still batch
+/// together.
+pub struct SpriteBatch {
+    vertex_buffer: glium::VertexBuffer<SpriteVertex>,
+    index_buffer: glium::IndexBuffer<u32>,
+    texture_array: glium::texture::SrgbTexture2dArray,
+    pending: Vec<Sprite>,
+}
+
+impl SpriteBatch {
+    pub fn new(
+        display: &glium::Display,
+        texture_array: glium::texture::SrgbTexture2dArray,
+    ) -> SpriteBatch {
+        let vertex_buffer =
+            glium::VertexBuffer::empty_dynamic(display, MAX_SPRITES_PER_BATCH * VERTS_PER_SPRITE)
+                .unwrap();
+
+        // the (0,1,2, 1,3,2) pattern repeated for every sprite slot, computed once up front
+        let mut indices = Vec::with_capacity(MAX_SPRITES_PER_BATCH * INDICES_PER_SPRITE);
+        for sprite in 0..MAX_SPRITES_PER_BATCH {
+            let base = (sprite * VERTS_PER_SPRITE) as u32;
+            indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+        }
+        let index_buffer = glium::IndexBuffer::new(
+            display,
+            glium::index::PrimitiveType::TrianglesList,
+            &indices,
+        )
+        .unwrap();
+
+        SpriteBatch {
+            vertex_buffer,
+            index_buffer,
+            texture_array,
+            pending: vec![],
+        }
+    }
+
+    pub fn push(&mut self, sprite: Sprite) {
+        self.pending.push(sprite);
+    }
+
+    /// Uploads and draws every pending sprite, splitting into multiple batches of
+    /// `MAX_SPRITES_PER_BATCH` sprites when the cap is exceeded, then clears the pending list.
+    pub fn flush(&mut self, target: &mut impl glium::Surface, program: &glium::Program) {
+        for chunk in self.pending.chunks(MAX_SPRITES_PER_BATCH) {
+            let mut verts = Vec::with_capacity(chunk.len() * VERTS_PER_SPRITE);
+            for sprite in chunk {
+                let [x, y] = sprite.pos;
+                let [w, h] = sprite.size;
+                let [u0, v0, u1, v1] = sprite.uv_rect;
+                verts.push(SpriteVertex {
+                    pos: [x, y],
+                    uv: [u0, v0],
+                    layer: sprite.layer,
+                });
+                verts.push(SpriteVertex {
+                    pos: [x + w, y],
+                    uv: [u1, v0],
+                    layer: sprite.layer,
+                });
+                verts.push(SpriteVertex {
+                    pos: [x, y + h],
+                    uv: [u0, v1],
+                    layer: sprite.layer,
+                });
+                verts.push(SpriteVertex {
+                    pos: [x + w, y + h],
+                    uv: [u1, v1],
+                    layer: sprite.layer,
+                });
+            }
+
+            let vertex_slice = self.vertex_buffer.slice(0..verts.len()).unwrap();
+            vertex_slice.write(&verts);
+            let index_slice = self
+                .index_buffer
+                .slice(0..(chunk.len() * INDICES_PER_SPRITE))
+                .unwrap();
+
+            let uniforms = glium::uniform! {
+                u_textures: self.texture_array.sampled(),
+            };
+            target
+                .draw(
+                    vertex_slice,
+                    index_slice,
+                    program,
+                    &uniforms,
+                    &Default::default(),
+                )
+                .unwrap();
+        }
+
+        self.pending.clear();
+    }
+}